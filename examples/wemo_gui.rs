@@ -4,12 +4,12 @@ extern crate wemo;
 
 use eframe::{egui, App, CreationContext, Frame, NativeOptions};
 use egui::{Color32, Context, RichText, Ui};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration as StdDuration;
 use time::Duration;
-use wemo::{DeviceSearch, Switch};
+use wemo::{DeviceHandle, DeviceSearch, DeviceType, Subscription, Switch};
 
 // Structure to hold device information
 struct DeviceInfo {
@@ -17,8 +17,14 @@ struct DeviceInfo {
     ip_address: std::net::IpAddr,
     port: u16,
     serial_number: String,
+    device_type: DeviceType,
     state: Option<wemo::WemoState>,
     status_message: String,
+    insight: Option<wemo::InsightParams>,
+    // Pushes a new state the moment the device reports a change, so the UI doesn't need to
+    // poll for on/off transitions. Carried over across rescans instead of being recreated, so
+    // re-scanning doesn't stack up a new subscription (and its background threads) per click.
+    events: Option<Subscription>,
 }
 
 // Main application structure
@@ -48,72 +54,135 @@ impl WemoApp {
         }
     }
 
-    // Find all WeMo devices on the network
+    // Find all WeMo devices on the network, updating rows in place as each one responds
+    // instead of blanking the list up front. Devices that were present before this scan but
+    // don't respond this time are dropped only once the scan is over.
     fn scan_for_devices(devices: Arc<Mutex<HashMap<String, DeviceInfo>>>) {
-        // Clear existing devices
-        devices.lock().unwrap().clear();
+        let previously_seen: HashSet<String> = devices.lock().unwrap().keys().cloned().collect();
+        let mut seen_this_scan = HashSet::new();
 
         let mut search = DeviceSearch::new();
-        let results = search.search(5_000); // 5 second timeout
+        let found = search.search_stream(5_000); // 5 second timeout
+
+        for device in found {
+            // Carry over an already-live subscription instead of subscribing again, so
+            // repeated scans don't stack up a new listener/renewal thread pair per device.
+            let existing_events = devices
+                .lock()
+                .unwrap()
+                .get_mut(&device.serial_number)
+                .and_then(|info| info.events.take());
+
+            let device_info = if device.device_type == DeviceType::Sensor {
+                Self::build_sensor_info(&device)
+            } else {
+                Self::build_switch_info(&device, existing_events)
+            };
+
+            seen_this_scan.insert(device.serial_number.clone());
+            devices
+                .lock()
+                .unwrap()
+                .insert(device.serial_number.clone(), device_info);
+        }
 
         let mut devices_map = devices.lock().unwrap();
+        for stale in previously_seen.difference(&seen_this_scan) {
+            devices_map.remove(stale);
+        }
+    }
 
-        for (key, device) in results.iter() {
-            let switch = Switch::from_dynamic_ip_and_port(device.ip_address, device.port);
-            let name = switch.name();
+    // Builds device info for an on/off-capable device (switch, Insight, light switch, Maker).
+    // Reuses `existing_events` if the caller already had a live subscription for this device.
+    fn build_switch_info(device: &wemo::Device, existing_events: Option<Subscription>) -> DeviceInfo {
+        let switch = Switch::from_dynamic_ip_and_port(device.ip_address, device.port);
+        let name = switch.name();
+
+        let state = switch.get_state_with_retry(Duration::seconds(3)).ok();
+        let status_message = match &state {
+            Some(s) => if s.is_on() { "ON" } else { "OFF" }.to_string(),
+            None => "Unknown".to_string(),
+        };
+        let insight = switch.get_insight_params().ok();
+        let events = existing_events.or_else(|| switch.subscribe().ok());
+
+        DeviceInfo {
+            name,
+            ip_address: device.ip_address,
+            port: device.port,
+            serial_number: device.serial_number.clone(),
+            device_type: device.device_type,
+            state,
+            status_message,
+            insight,
+            events,
+        }
+    }
 
-            // Get the initial state
-            let state = switch.get_state_with_retry(Duration::seconds(3)).ok();
-            let status_message = match &state {
-                Some(s) => if s.is_on() { "ON" } else { "OFF" }.to_string(),
-                None => "Unknown".to_string(),
-            };
+    // Builds device info for a motion sensor, which reports motion rather than on/off.
+    fn build_sensor_info(device: &wemo::Device) -> DeviceInfo {
+        let handle = DeviceHandle::from_dynamic_ip_and_port(device.ip_address, device.port);
+        let sensor = match handle {
+            Ok(DeviceHandle::Sensor(sensor)) => Some(sensor),
+            _ => None,
+        };
+
+        let motion = sensor.as_ref().and_then(|s| s.motion_detected().ok());
+        let status_message = match motion {
+            Some(true) => "MOTION".to_string(),
+            Some(false) => "CLEAR".to_string(),
+            None => "Unknown".to_string(),
+        };
+
+        DeviceInfo {
+            name: format!("Motion Sensor ({})", device.serial_number),
+            ip_address: device.ip_address,
+            port: device.port,
+            serial_number: device.serial_number.clone(),
+            device_type: device.device_type,
+            state: None,
+            status_message,
+            insight: None,
+            events: None,
+        }
+    }
 
-            // Add to our device map
-            devices_map.insert(
-                key.clone(),
-                DeviceInfo {
-                    name,
-                    ip_address: device.ip_address,
-                    port: device.port,
-                    serial_number: device.serial_number.clone(),
-                    state,
-                    status_message,
-                },
-            );
+    // Apply any state pushed by a device's event subscription since the last frame. This is
+    // cheap (non-blocking) and safe to call every frame.
+    fn drain_events(&self) {
+        let mut devices_map = self.devices.lock().unwrap();
+
+        for device_info in devices_map.values_mut() {
+            if let Some(events) = &device_info.events {
+                while let Ok(state) = events.try_recv() {
+                    device_info.status_message =
+                        if state.is_on() { "ON" } else { "OFF" }.to_string();
+                    device_info.state = Some(state);
+                }
+            }
         }
     }
 
-    // Refresh the state of all devices
+    // Refresh Insight power readings, which aren't pushed by the device's event subscription
+    // and still need polling.
     fn refresh_states(&mut self) {
         let devices_clone = Arc::clone(&self.devices);
 
         thread::spawn(move || {
-            let devices_map = devices_clone.lock().unwrap();
-
-            for (key, device_info) in devices_map.iter() {
-                let switch =
-                    Switch::from_dynamic_ip_and_port(device_info.ip_address, device_info.port);
-
-                // Use a separate thread for each device to avoid blocking
-                let key_clone = key.clone();
-                let devices_clone_inner = Arc::clone(&devices_clone);
-
-                thread::spawn(move || {
-                    let state = switch.get_state_with_retry(Duration::seconds(3)).ok();
-
-                    // Update device state
-                    let mut devices_map = devices_clone_inner.lock().unwrap();
-                    if let Some(device) = devices_map.get_mut(&key_clone) {
-                        device.state = state;
-                        if let Some(s) = &device.state {
-                            device.status_message =
-                                if s.is_on() { "ON" } else { "OFF" }.to_string();
-                        } else {
-                            device.status_message = "Unknown".to_string();
-                        }
-                    }
-                });
+            let addresses: Vec<(String, std::net::IpAddr, u16)> = devices_clone
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(key, info)| (key.clone(), info.ip_address, info.port))
+                .collect();
+
+            for (key, ip_address, port) in addresses {
+                let switch = Switch::from_dynamic_ip_and_port(ip_address, port);
+                let insight = switch.get_insight_params().ok();
+
+                if let Some(device_info) = devices_clone.lock().unwrap().get_mut(&key) {
+                    device_info.insight = insight;
+                }
             }
         });
     }
@@ -141,7 +210,10 @@ impl WemoApp {
 
 impl App for WemoApp {
     fn update(&mut self, ctx: &Context, _frame: &mut Frame) {
-        // Auto-refresh device states
+        // Apply any pushed state changes immediately, every frame.
+        self.drain_events();
+
+        // Insight watts aren't pushed, so still poll for those periodically.
         let now = std::time::Instant::now();
         if now.duration_since(self.last_refresh).as_secs() >= self.refresh_interval {
             self.refresh_states();
@@ -170,9 +242,9 @@ impl App for WemoApp {
 
             ui.add_space(10.0);
 
-            // Refresh interval slider
+            // Insight (watts) refresh interval slider
             ui.horizontal(|ui| {
-                ui.label("Refresh interval:");
+                ui.label("Insight refresh interval:");
                 ui.add(egui::Slider::new(&mut self.refresh_interval, 5..=60).suffix(" sec"));
             });
 
@@ -220,39 +292,48 @@ impl WemoApp {
                         // Status with color
                         let status_text = format!("Status: {}", &device_info.status_message);
                         let status_color = match device_info.status_message.as_str() {
-                            "ON" => Color32::GREEN,
-                            "OFF" => Color32::RED,
+                            "ON" | "MOTION" => Color32::GREEN,
+                            "OFF" | "CLEAR" => Color32::RED,
                             _ => Color32::GRAY,
                         };
                         ui.label(RichText::new(status_text).color(status_color).strong());
-                    });
 
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        // On/Off buttons
-                        let is_on = device_info.state.as_ref().map_or(false, |s| s.is_on());
-
-                        // OFF button
-                        let off_button = ui.add_enabled(
-                            is_on,
-                            egui::Button::new(RichText::new("Turn OFF").color(Color32::WHITE))
-                                .fill(Color32::RED),
-                        );
-
-                        if off_button.clicked() {
-                            self.toggle_device(device_info, false);
-                        }
-
-                        // ON button
-                        let on_button = ui.add_enabled(
-                            !is_on,
-                            egui::Button::new(RichText::new("Turn ON").color(Color32::WHITE))
-                                .fill(Color32::GREEN),
-                        );
-
-                        if on_button.clicked() {
-                            self.toggle_device(device_info, true);
+                        if let Some(insight) = &device_info.insight {
+                            ui.label(format!(
+                                "Power: {:.1} W",
+                                insight.current_power_mw as f64 / 1000.0
+                            ));
                         }
                     });
+
+                    // Motion sensors are read-only; only switch-like devices get on/off buttons.
+                    if device_info.device_type != DeviceType::Sensor {
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            let is_on = device_info.state.as_ref().is_some_and(|s| s.is_on());
+
+                            // OFF button
+                            let off_button = ui.add_enabled(
+                                is_on,
+                                egui::Button::new(RichText::new("Turn OFF").color(Color32::WHITE))
+                                    .fill(Color32::RED),
+                            );
+
+                            if off_button.clicked() {
+                                self.toggle_device(device_info, false);
+                            }
+
+                            // ON button
+                            let on_button = ui.add_enabled(
+                                !is_on,
+                                egui::Button::new(RichText::new("Turn ON").color(Color32::WHITE))
+                                    .fill(Color32::GREEN),
+                            );
+
+                            if on_button.clicked() {
+                                self.toggle_device(device_info, true);
+                            }
+                        });
+                    }
                 });
             });
             ui.add_space(4.0);
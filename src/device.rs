@@ -0,0 +1,35 @@
+use std::net::IpAddr;
+
+use crate::device_type::DeviceType;
+use crate::error::{Error, Result};
+use crate::sensor::MotionSensor;
+use crate::soap;
+use crate::switch::Switch;
+
+/// A handle to a device discovered via its `setup.xml`, typed according to its `deviceType`.
+pub enum DeviceHandle {
+    /// A switch-like device: a plug, Insight socket, in-wall light switch, or Maker.
+    Switch(Switch),
+    /// A motion sensor.
+    Sensor(MotionSensor),
+}
+
+impl DeviceHandle {
+    /// Fetches `setup.xml` from the device at `ip_address:port` and returns a handle typed
+    /// according to its `deviceType`, with control URLs taken from its `serviceList`.
+    pub fn from_dynamic_ip_and_port(ip_address: IpAddr, port: u16) -> Result<Self> {
+        let url = format!("http://{ip_address}:{port}/setup.xml");
+        let body = ureq::get(&url).call()?.into_string()?;
+
+        let device_type = soap::extract_tag(&body, "deviceType")
+            .map(DeviceType::from_urn)
+            .ok_or(Error::MissingField("deviceType"))?;
+
+        let switch = Switch::from_setup_xml(ip_address, port, &body);
+
+        Ok(match device_type {
+            DeviceType::Sensor => DeviceHandle::Sensor(MotionSensor::new(switch)),
+            _ => DeviceHandle::Switch(switch),
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use std::fmt;
+
+/// The kind of physical WeMo device behind a handle, inferred from its `deviceType` URN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceType {
+    /// A basic switch/socket (`urn:Belkin:device:controllee:1`).
+    Controllee,
+    /// An Insight energy-monitoring socket (`urn:Belkin:device:insight:1`).
+    Insight,
+    /// An in-wall light switch (`urn:Belkin:device:lightswitch:1`).
+    LightSwitch,
+    /// A motion sensor (`urn:Belkin:device:sensor:1`).
+    Sensor,
+    /// A Maker I/O device (`urn:Belkin:device:Maker:1`).
+    Maker,
+    /// A device type this crate doesn't recognize yet.
+    Unknown,
+}
+
+impl DeviceType {
+    /// Classifies a `deviceType` URN as read from a device's `setup.xml`.
+    pub(crate) fn from_urn(urn: &str) -> Self {
+        match urn {
+            "urn:Belkin:device:controllee:1" => DeviceType::Controllee,
+            "urn:Belkin:device:insight:1" => DeviceType::Insight,
+            "urn:Belkin:device:lightswitch:1" => DeviceType::LightSwitch,
+            "urn:Belkin:device:sensor:1" => DeviceType::Sensor,
+            "urn:Belkin:device:Maker:1" => DeviceType::Maker,
+            _ => DeviceType::Unknown,
+        }
+    }
+}
+
+impl fmt::Display for DeviceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DeviceType::Controllee => "Switch",
+            DeviceType::Insight => "Insight",
+            DeviceType::LightSwitch => "Light Switch",
+            DeviceType::Sensor => "Motion Sensor",
+            DeviceType::Maker => "Maker",
+            DeviceType::Unknown => "Unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_known_device_urns() {
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:controllee:1"),
+            DeviceType::Controllee
+        );
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:insight:1"),
+            DeviceType::Insight
+        );
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:lightswitch:1"),
+            DeviceType::LightSwitch
+        );
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:sensor:1"),
+            DeviceType::Sensor
+        );
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:Maker:1"),
+            DeviceType::Maker
+        );
+    }
+
+    #[test]
+    fn falls_back_to_unknown_for_unrecognized_urns() {
+        assert_eq!(
+            DeviceType::from_urn("urn:Belkin:device:somethingnew:1"),
+            DeviceType::Unknown
+        );
+        assert_eq!(DeviceType::from_urn(""), DeviceType::Unknown);
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::device_type::DeviceType;
+use crate::soap;
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SEARCH_TARGET: &str = "urn:Belkin:device:**";
+
+fn msearch_request() -> String {
+    format!(
+        "M-SEARCH * HTTP/1.1\r\n\
+         HOST: 239.255.255.250:1900\r\n\
+         MAN: \"ssdp:discover\"\r\n\
+         ST: {SEARCH_TARGET}\r\n\
+         MX: 3\r\n\r\n"
+    )
+}
+
+/// A WeMo device discovered on the local network via SSDP.
+#[derive(Debug, Clone)]
+pub struct Device {
+    pub ip_address: IpAddr,
+    pub port: u16,
+    pub serial_number: String,
+    pub device_type: DeviceType,
+}
+
+/// A device yielded by [`DeviceSearch::search_stream`], the moment its response and `setup.xml`
+/// fetch complete.
+pub type DiscoveredDevice = Device;
+
+/// Searches the local network for WeMo devices using UPnP SSDP discovery.
+pub struct DeviceSearch {
+    socket: UdpSocket,
+}
+
+impl DeviceSearch {
+    /// Creates a new device search, bound to an ephemeral local UDP port.
+    pub fn new() -> Self {
+        let socket = UdpSocket::bind("0.0.0.0:0").expect("failed to bind SSDP socket");
+        socket.set_broadcast(true).ok();
+        Self { socket }
+    }
+
+    /// Searches for WeMo devices for `timeout_ms` milliseconds, returning everything found,
+    /// keyed by serial number.
+    ///
+    /// This blocks for the full `timeout_ms` and only returns once the search is over; for
+    /// incremental results as devices respond, use [`DeviceSearch::search_stream`] instead.
+    pub fn search(&mut self, timeout_ms: u64) -> HashMap<String, Device> {
+        self.search_stream(timeout_ms)
+            .iter()
+            .map(|device| (device.serial_number.clone(), device))
+            .collect()
+    }
+
+    /// Sends the SSDP `M-SEARCH` and streams back each device the moment its response and
+    /// `setup.xml` fetch complete, rather than waiting for the whole `timeout_ms` to collect
+    /// them all. The returned channel closes once the timeout elapses.
+    pub fn search_stream(&mut self, timeout_ms: u64) -> Receiver<DiscoveredDevice> {
+        let (tx, rx) = mpsc::channel();
+        let socket = self.socket.try_clone().expect("failed to clone SSDP socket");
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+
+        let dest: SocketAddr = SSDP_ADDR.parse().expect("valid SSDP address");
+        let request = msearch_request();
+        let _ = socket.send_to(request.as_bytes(), dest);
+
+        thread::spawn(move || {
+            let mut buf = [0u8; 2048];
+            loop {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    break;
+                }
+                socket.set_read_timeout(Some(remaining)).ok();
+
+                match socket.recv_from(&mut buf) {
+                    Ok((len, addr)) => {
+                        let response = String::from_utf8_lossy(&buf[..len]);
+                        if let Some(device) = parse_response(&response, addr.ip()) {
+                            if tx.send(device).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        rx
+    }
+}
+
+impl Default for DeviceSearch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pulls the `LOCATION` header out of an SSDP response, takes the device's port from it, and
+/// fetches `setup.xml` from it to learn the device's serial number and type.
+fn parse_response(response: &str, ip: IpAddr) -> Option<Device> {
+    let location = response
+        .lines()
+        .find(|line| line.to_ascii_lowercase().starts_with("location:"))?
+        .split_once(':')?
+        .1
+        .trim();
+
+    let port = location
+        .rsplit_once(':')
+        .and_then(|(_, rest)| rest.split('/').next())
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(49153);
+
+    let body = ureq::get(location).call().ok()?.into_string().ok()?;
+    let serial_number = soap::extract_tag(&body, "serialNumber")?.to_string();
+    let device_type = soap::extract_tag(&body, "deviceType")
+        .map(DeviceType::from_urn)
+        .unwrap_or(DeviceType::Unknown);
+
+    Some(Device {
+        ip_address: ip,
+        port,
+        serial_number,
+        device_type,
+    })
+}
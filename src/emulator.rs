@@ -0,0 +1,265 @@
+//! Makes user-supplied devices appear on the LAN as real WeMo switches, so that voice
+//! assistants like an Amazon Echo can discover and toggle them.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+
+use uuid::Uuid;
+
+use crate::error::Result;
+use crate::soap;
+use crate::util::local_ip_towards;
+
+const SSDP_ADDR: Ipv4Addr = Ipv4Addr::new(239, 255, 255, 250);
+const SSDP_PORT: u16 = 1900;
+const SERVICE_BASIC_EVENT: &str = "urn:Belkin:service:basicevent:1";
+const CONTROL_BASIC_EVENT: &str = "/upnp/control/basicevent1";
+
+/// A single device exposed on the LAN, backed by user-supplied callbacks.
+///
+/// An incoming `SetBinaryState` calls `turn_on`/`turn_off`; `GetBinaryState` calls
+/// `get_state`. All three run on whichever thread is handling that request, so they should be
+/// cheap and non-blocking.
+pub struct VirtualSwitch {
+    name: String,
+    port: u16,
+    uuid: String,
+    turn_on: Box<dyn Fn() + Send + Sync>,
+    turn_off: Box<dyn Fn() + Send + Sync>,
+    get_state: Box<dyn Fn() -> bool + Send + Sync>,
+}
+
+impl VirtualSwitch {
+    /// Creates a virtual WeMo switch named `name`, served on `port`, backed by the given
+    /// callbacks.
+    pub fn new(
+        name: impl Into<String>,
+        port: u16,
+        turn_on: impl Fn() + Send + Sync + 'static,
+        turn_off: impl Fn() + Send + Sync + 'static,
+        get_state: impl Fn() -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            port,
+            uuid: Uuid::new_v4().to_string(),
+            turn_on: Box::new(turn_on),
+            turn_off: Box::new(turn_off),
+            get_state: Box::new(get_state),
+        }
+    }
+
+    fn setup_xml(&self) -> String {
+        format!(
+            "<?xml version=\"1.0\"?>\
+<root xmlns=\"urn:Belkin:device-1-0\">\
+<device>\
+<deviceType>urn:Belkin:device:controllee:1</deviceType>\
+<friendlyName>{name}</friendlyName>\
+<manufacturer>Belkin International Inc.</manufacturer>\
+<modelName>Emulated Socket</modelName>\
+<UDN>uuid:Socket-1_0-{uuid}</UDN>\
+<serviceList>\
+<service>\
+<serviceType>{SERVICE_BASIC_EVENT}</serviceType>\
+<serviceId>urn:Belkin:serviceId:basicevent1</serviceId>\
+<controlURL>{CONTROL_BASIC_EVENT}</controlURL>\
+<eventSubURL>/upnp/event/basicevent1</eventSubURL>\
+<SCPDURL>/eventservice.xml</SCPDURL>\
+</service>\
+</serviceList>\
+</device>\
+</root>",
+            name = self.name,
+            uuid = self.uuid,
+        )
+    }
+}
+
+/// Hosts one or more [`VirtualSwitch`] devices on the LAN so they can be discovered (via SSDP)
+/// and controlled (via SOAP) exactly like a real WeMo switch.
+#[derive(Default)]
+pub struct Emulator {
+    devices: Vec<VirtualSwitch>,
+}
+
+impl Emulator {
+    /// Creates an emulator with no devices registered yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a device to be hosted when [`Emulator::run`] is called. Each device must use
+    /// a distinct port.
+    pub fn register(&mut self, device: VirtualSwitch) {
+        self.devices.push(device);
+    }
+
+    /// Starts an HTTP server per registered device plus one shared SSDP responder, and blocks
+    /// the calling thread forever.
+    pub fn run(self) -> Result<()> {
+        let devices: Vec<Arc<VirtualSwitch>> = self.devices.into_iter().map(Arc::new).collect();
+
+        for device in &devices {
+            let device = Arc::clone(device);
+            thread::spawn(move || run_http_server(&device));
+        }
+
+        run_ssdp_responder(&devices)
+    }
+}
+
+fn run_http_server(device: &Arc<VirtualSwitch>) {
+    let Ok(listener) = TcpListener::bind(("0.0.0.0", device.port)) else {
+        return;
+    };
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else { continue };
+        let device = Arc::clone(device);
+        thread::spawn(move || {
+            let _ = handle_http_request(stream, &device);
+        });
+    }
+}
+
+fn handle_http_request(mut stream: TcpStream, device: &VirtualSwitch) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0usize;
+    let mut soap_action = String::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = trimmed.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            } else if name.eq_ignore_ascii_case("soapaction") {
+                soap_action = value.trim().to_string();
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+
+    match (method.as_str(), path.as_str()) {
+        ("GET", "/setup.xml") => respond(&mut stream, "200 OK", "text/xml", &device.setup_xml()),
+        ("POST", CONTROL_BASIC_EVENT) => handle_soap(&mut stream, device, &soap_action, &body),
+        _ => respond(&mut stream, "404 Not Found", "text/plain", ""),
+    }
+}
+
+fn handle_soap(
+    stream: &mut TcpStream,
+    device: &VirtualSwitch,
+    soap_action: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = if soap_action.contains("SetBinaryState") {
+        if let Some(state) = soap::extract_tag(body, "BinaryState") {
+            if state.trim() == "1" {
+                (device.turn_on)();
+            } else {
+                (device.turn_off)();
+            }
+        }
+        soap_response(
+            "SetBinaryState",
+            &format!("<BinaryState>{}</BinaryState>", (device.get_state)() as i32),
+        )
+    } else {
+        soap_response(
+            "GetBinaryState",
+            &format!("<BinaryState>{}</BinaryState>", (device.get_state)() as i32),
+        )
+    };
+
+    respond(stream, "200 OK", "text/xml", &response)
+}
+
+fn soap_response(action: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\
+<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+<s:Body><u:{action}Response xmlns:u=\"{SERVICE_BASIC_EVENT}\">{body}</u:{action}Response></s:Body>\
+</s:Envelope>"
+    )
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {len}\r\nConnection: close\r\n\r\n{body}",
+        len = body.len()
+    )
+}
+
+/// Listens for SSDP `M-SEARCH` requests targeting `urn:Belkin:device:**` and answers with the
+/// `LOCATION` of every registered device.
+fn run_ssdp_responder(devices: &[Arc<VirtualSwitch>]) -> Result<()> {
+    let socket = UdpSocket::bind(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, SSDP_PORT))?;
+    socket.join_multicast_v4(&SSDP_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+
+    let mut buf = [0u8; 2048];
+    loop {
+        let (len, src) = match socket.recv_from(&mut buf) {
+            Ok(result) => result,
+            Err(_) => continue,
+        };
+
+        let request = String::from_utf8_lossy(&buf[..len]);
+        if !is_wemo_msearch(&request) {
+            continue;
+        }
+
+        let Ok(local_ip) = local_ip_towards(src.ip(), src.port()) else {
+            continue;
+        };
+
+        for device in devices {
+            let location = format!("http://{local_ip}:{}/setup.xml", device.port);
+            let response = msearch_response(&location, &device.uuid);
+            let _ = socket.send_to(response.as_bytes(), src);
+        }
+    }
+}
+
+fn is_wemo_msearch(request: &str) -> bool {
+    let is_msearch = request
+        .lines()
+        .next()
+        .is_some_and(|line| line.starts_with("M-SEARCH"));
+
+    is_msearch
+        && request.lines().any(|line| {
+            let upper = line.to_ascii_uppercase();
+            upper.starts_with("ST:") && upper.contains("BELKIN:DEVICE")
+        })
+}
+
+fn msearch_response(location: &str, uuid: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\n\
+         CACHE-CONTROL: max-age=86400\r\n\
+         LOCATION: {location}\r\n\
+         ST: urn:Belkin:device:**\r\n\
+         USN: uuid:Socket-1_0-{uuid}::urn:Belkin:device:**\r\n\r\n"
+    )
+}
@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// Errors that can occur while talking to a WeMo device.
+#[derive(Debug)]
+pub enum Error {
+    /// The underlying HTTP request failed (network error, timeout, non-2xx status, ...).
+    Http(Box<ureq::Error>),
+    /// The device's response body could not be read.
+    Io(std::io::Error),
+    /// The SOAP response did not contain the expected XML element.
+    MissingField(&'static str),
+    /// A field in the SOAP response could not be parsed into the expected type.
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "HTTP request failed: {e}"),
+            Error::Io(e) => write!(f, "failed to read response body: {e}"),
+            Error::MissingField(field) => write!(f, "response missing expected field `{field}`"),
+            Error::InvalidField(field) => write!(f, "response field `{field}` could not be parsed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ureq::Error> for Error {
+    fn from(e: ureq::Error) -> Self {
+        Error::Http(Box::new(e))
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// A `Result` alias using [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use crate::error::Error;
+
+/// Power-usage data read from a WeMo Insight plug via `GetInsightParams`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InsightParams {
+    /// Whether the device is currently switched on.
+    pub state: bool,
+    /// Unix timestamp (seconds) of the last state change.
+    pub last_changed: i64,
+    /// How long the device has been in its current state.
+    pub on_for: Duration,
+    /// How long the device has been on today.
+    pub on_today: Duration,
+    /// How long the device has been on in total since its counters were last reset.
+    pub on_total: Duration,
+    /// Instantaneous power draw, in milliwatts.
+    pub current_power_mw: u32,
+    /// Energy used today, in milliwatt-minutes.
+    pub today_energy_mw_min: u64,
+}
+
+impl InsightParams {
+    /// Energy used today, converted from milliwatt-minutes to watt-hours.
+    pub fn today_energy_wh(&self) -> f64 {
+        self.today_energy_mw_min as f64 / 60_000.0
+    }
+
+    /// Parses the pipe-delimited value of an `<InsightParams>` element, e.g.
+    /// `1|1678901234|120|3600|86400|1209600|50|7500|123456|...`.
+    pub(crate) fn parse(raw: &str) -> Result<Self, Error> {
+        let fields: Vec<&str> = raw.trim().split('|').collect();
+        let field = |index: usize| -> Result<&str, Error> {
+            fields
+                .get(index)
+                .copied()
+                .ok_or(Error::MissingField("InsightParams"))
+        };
+        let parse_u64 = |index: usize| -> Result<u64, Error> {
+            field(index)?
+                .parse()
+                .map_err(|_| Error::InvalidField("InsightParams"))
+        };
+
+        Ok(InsightParams {
+            state: parse_u64(0)? != 0,
+            last_changed: field(1)?
+                .parse()
+                .map_err(|_| Error::InvalidField("InsightParams"))?,
+            on_for: Duration::from_secs(parse_u64(2)?),
+            on_today: Duration::from_secs(parse_u64(3)?),
+            on_total: Duration::from_secs(parse_u64(4)?),
+            current_power_mw: parse_u64(7)? as u32,
+            today_energy_mw_min: parse_u64(8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_well_formed_insight_params_string() {
+        let raw = "1|1678901234|120|3600|86400|1209600|50|7500|123456";
+        let params = InsightParams::parse(raw).unwrap();
+
+        assert!(params.state);
+        assert_eq!(params.last_changed, 1678901234);
+        assert_eq!(params.on_for, Duration::from_secs(120));
+        assert_eq!(params.on_today, Duration::from_secs(3600));
+        assert_eq!(params.on_total, Duration::from_secs(86400));
+        assert_eq!(params.current_power_mw, 7500);
+        assert_eq!(params.today_energy_mw_min, 123456);
+        assert_eq!(params.today_energy_wh(), 123456.0 / 60_000.0);
+    }
+
+    #[test]
+    fn rejects_a_truncated_insight_params_string() {
+        let err = InsightParams::parse("1|1678901234|120").unwrap_err();
+        assert!(matches!(err, Error::MissingField("InsightParams")));
+    }
+
+    #[test]
+    fn rejects_a_non_numeric_field() {
+        let raw = "1|not-a-number|120|3600|86400|0|0|7500|123456";
+        let err = InsightParams::parse(raw).unwrap_err();
+        assert!(matches!(err, Error::InvalidField("InsightParams")));
+    }
+}
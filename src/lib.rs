@@ -0,0 +1,32 @@
+//! A Rust client library for discovering and controlling Belkin WeMo switches over the local
+//! network, using their UPnP/SOAP control interface.
+//!
+//! Start with [`DeviceSearch`] to find devices on the LAN, then talk to one with [`Switch`].
+//! Devices of different kinds (switches, Insight sockets, motion sensors, ...) can be told
+//! apart and handled through [`DeviceHandle`].
+
+mod device;
+mod device_type;
+mod discovery;
+mod emulator;
+mod error;
+mod insight;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+mod sensor;
+mod soap;
+mod state;
+mod subscription;
+mod switch;
+mod util;
+
+pub use device::DeviceHandle;
+pub use device_type::DeviceType;
+pub use discovery::{Device, DeviceSearch, DiscoveredDevice};
+pub use emulator::{Emulator, VirtualSwitch};
+pub use error::{Error, Result};
+pub use insight::InsightParams;
+pub use sensor::MotionSensor;
+pub use state::WemoState;
+pub use subscription::Subscription;
+pub use switch::Switch;
@@ -0,0 +1,196 @@
+//! An optional bridge that publishes discovered WeMo devices to an MQTT broker using Home
+//! Assistant's MQTT discovery conventions, so they show up in Home Assistant without any
+//! manual `configuration.yaml` entries. Enable this module with the `mqtt` feature.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration as StdDuration;
+
+use rumqttc::{Client, Event, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use time::Duration;
+
+use crate::discovery::DeviceSearch;
+use crate::error::{Error, Result};
+use crate::insight::InsightParams;
+use crate::state::WemoState;
+use crate::switch::Switch;
+
+const DISCOVERY_TIMEOUT_MS: u64 = 5_000;
+
+/// Configuration for [`run`], typically loaded from a YAML file with
+/// [`BridgeConfig::from_file`].
+#[derive(Debug, Deserialize)]
+pub struct BridgeConfig {
+    pub broker_host: String,
+    #[serde(default = "default_broker_port")]
+    pub broker_port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default = "default_base_topic")]
+    pub base_topic: String,
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+}
+
+fn default_broker_port() -> u16 {
+    1883
+}
+
+fn default_base_topic() -> String {
+    "wemo".to_string()
+}
+
+fn default_poll_interval_secs() -> u64 {
+    30
+}
+
+impl BridgeConfig {
+    /// Loads bridge configuration from a YAML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_yaml::from_str(&contents).map_err(|_| Error::InvalidField("mqtt config"))
+    }
+}
+
+/// Discovers WeMo devices on the LAN, publishes Home Assistant MQTT-discovery messages for
+/// each, and bridges their state to `<base_topic>/<serial>/state` (driven by
+/// `<base_topic>/<serial>/set`). Blocks the calling thread forever; intended to run as a
+/// headless daemon.
+pub fn run(config: BridgeConfig) -> Result<()> {
+    let mut search = DeviceSearch::new();
+    let discovered = search.search(DISCOVERY_TIMEOUT_MS);
+
+    let mut opts = MqttOptions::new("wemo-bridge", config.broker_host.clone(), config.broker_port);
+    opts.set_keep_alive(StdDuration::from_secs(30));
+    if let (Some(username), Some(password)) = (&config.username, &config.password) {
+        opts.set_credentials(username.clone(), password.clone());
+    }
+
+    let (client, connection) = Client::new(opts, 16);
+
+    let switches: HashMap<String, Switch> = discovered
+        .iter()
+        .map(|(serial, device)| {
+            (
+                serial.clone(),
+                Switch::from_dynamic_ip_and_port(device.ip_address, device.port),
+            )
+        })
+        .collect();
+
+    for (serial, switch) in &switches {
+        publish_discovery(&client, &config.base_topic, serial, &switch.name());
+        let _ = client.subscribe(set_topic(&config.base_topic, serial), QoS::AtLeastOnce);
+    }
+
+    spawn_command_listener(connection, config.base_topic.clone(), switches.clone());
+
+    loop {
+        for (serial, switch) in &switches {
+            publish_state(
+                &client,
+                &config.base_topic,
+                serial,
+                switch.get_state().ok(),
+                switch.get_insight_params().ok(),
+            );
+        }
+        thread::sleep(StdDuration::from_secs(config.poll_interval_secs));
+    }
+}
+
+/// Runs the MQTT event loop on a background thread, driving `turn_on`/`turn_off` whenever a
+/// `.../set` message arrives for a known device. The caller keeps its own `Client` handle alive
+/// for publishing; this thread only needs the `Connection` half to poll incoming messages.
+fn spawn_command_listener(
+    mut connection: rumqttc::Connection,
+    base_topic: String,
+    switches: HashMap<String, Switch>,
+) {
+    thread::spawn(move || {
+        for notification in connection.iter() {
+            let Ok(Event::Incoming(Packet::Publish(publish))) = notification else {
+                continue;
+            };
+            let Some(serial) = serial_from_set_topic(&base_topic, &publish.topic) else {
+                continue;
+            };
+            let Some(switch) = switches.get(&serial) else {
+                continue;
+            };
+
+            let timeout = Duration::seconds(5);
+            match publish.payload.as_ref() {
+                b"ON" => {
+                    let _ = switch.turn_on_with_retry(timeout);
+                }
+                b"OFF" => {
+                    let _ = switch.turn_off_with_retry(timeout);
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// The Home Assistant MQTT-discovery config payload for a single switch entity.
+#[derive(Serialize)]
+struct DiscoveryConfig<'a> {
+    name: &'a str,
+    unique_id: &'a str,
+    command_topic: String,
+    state_topic: String,
+    payload_on: &'a str,
+    payload_off: &'a str,
+}
+
+fn publish_discovery(client: &Client, base_topic: &str, serial: &str, name: &str) {
+    let config_topic = format!("homeassistant/switch/{serial}/config");
+    let config = DiscoveryConfig {
+        name,
+        unique_id: serial,
+        command_topic: set_topic(base_topic, serial),
+        state_topic: format!("{base_topic}/{serial}/state"),
+        payload_on: "ON",
+        payload_off: "OFF",
+    };
+
+    if let Ok(payload) = serde_json::to_string(&config) {
+        let _ = client.publish(config_topic, QoS::AtLeastOnce, true, payload);
+    }
+}
+
+fn publish_state(
+    client: &Client,
+    base_topic: &str,
+    serial: &str,
+    state: Option<WemoState>,
+    insight: Option<InsightParams>,
+) {
+    if let Some(state) = state {
+        let state_topic = format!("{base_topic}/{serial}/state");
+        let payload = if state.is_on() { "ON" } else { "OFF" };
+        let _ = client.publish(state_topic, QoS::AtLeastOnce, true, payload);
+    }
+
+    if let Some(insight) = insight {
+        let power_topic = format!("{base_topic}/{serial}/power_w");
+        let watts = insight.current_power_mw as f64 / 1000.0;
+        let _ = client.publish(power_topic, QoS::AtLeastOnce, true, format!("{watts:.1}"));
+    }
+}
+
+fn set_topic(base_topic: &str, serial: &str) -> String {
+    format!("{base_topic}/{serial}/set")
+}
+
+fn serial_from_set_topic(base_topic: &str, topic: &str) -> Option<String> {
+    let prefix = format!("{base_topic}/");
+    topic
+        .strip_prefix(&prefix)?
+        .strip_suffix("/set")
+        .map(str::to_string)
+}
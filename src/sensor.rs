@@ -0,0 +1,21 @@
+use crate::error::Result;
+use crate::switch::Switch;
+
+/// A handle to a WeMo motion sensor.
+///
+/// Motion sensors report their state through the same `basicevent` SOAP service a switch
+/// uses; `BinaryState` just means "motion currently detected" instead of "on".
+pub struct MotionSensor {
+    switch: Switch,
+}
+
+impl MotionSensor {
+    pub(crate) fn new(switch: Switch) -> Self {
+        Self { switch }
+    }
+
+    /// Returns `true` if motion is currently detected.
+    pub fn motion_detected(&self) -> Result<bool> {
+        self.switch.get_state().map(|state| state.is_on())
+    }
+}
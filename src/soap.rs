@@ -0,0 +1,65 @@
+//! Small helpers for issuing UPnP SOAP actions and picking values back out of the XML
+//! responses WeMo devices send back. WeMo's replies are simple and flat enough that pulling
+//! in a full XML parser isn't worth it; plain substring search is enough.
+
+use std::net::IpAddr;
+use std::time::Duration;
+
+use crate::error::Result;
+
+/// Sends a SOAP `action` to `service_type` at `control_path` on the device at `ip:port`, with
+/// `body` as the inner XML of the request, and returns the raw XML of the response.
+pub(crate) fn send_action(
+    ip: IpAddr,
+    port: u16,
+    control_path: &str,
+    service_type: &str,
+    action: &str,
+    body: &str,
+) -> Result<String> {
+    let url = format!("http://{ip}:{port}{control_path}");
+    let soap_action = format!("\"{service_type}#{action}\"");
+
+    let envelope = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\
+<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+<s:Body><u:{action} xmlns:u=\"{service_type}\">{body}</u:{action}></s:Body></s:Envelope>"
+    );
+
+    let response = ureq::post(&url)
+        .set("Content-Type", "text/xml; charset=\"utf-8\"")
+        .set("SOAPACTION", &soap_action)
+        .timeout(Duration::from_secs(10))
+        .send_string(&envelope)?;
+
+    Ok(response.into_string()?)
+}
+
+/// Returns the text content of the first `<tag>...</tag>` occurrence in `xml`, if any.
+pub(crate) fn extract_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    Some(&xml[start..end])
+}
+
+/// Returns the inner XML of every `<tag>...</tag>` block in `xml`, in document order.
+pub(crate) fn tag_blocks<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{tag}>");
+    let close = format!("</{tag}>");
+    let mut blocks = Vec::new();
+    let mut rest = xml;
+
+    while let Some(start) = rest.find(&open) {
+        let after_open = &rest[start + open.len()..];
+        let Some(end) = after_open.find(&close) else {
+            break;
+        };
+        blocks.push(&after_open[..end]);
+        rest = &after_open[end + close.len()..];
+    }
+
+    blocks
+}
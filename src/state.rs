@@ -0,0 +1,16 @@
+/// The on/off state of a WeMo switch, as reported by `GetBinaryState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WemoState {
+    on: bool,
+}
+
+impl WemoState {
+    pub(crate) fn new(on: bool) -> Self {
+        Self { on }
+    }
+
+    /// Returns `true` if the device is currently switched on.
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+}
@@ -0,0 +1,206 @@
+//! UPnP GENA event subscriptions.
+//!
+//! Instead of polling a device with `get_state_with_retry`, callers can [`Switch::subscribe`]
+//! and get a [`WemoState`] pushed to them the moment the device's state changes.
+
+use std::io::{Read, Write};
+use std::io::{BufRead, BufReader};
+use std::net::{IpAddr, TcpListener, TcpStream};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::soap;
+use crate::state::WemoState;
+use crate::switch::Switch;
+use crate::util::local_ip_towards;
+
+const EVENT_PATH: &str = "/upnp/event/basicevent1";
+const SUBSCRIPTION_TIMEOUT_SECS: u64 = 300;
+// How often the listener and renewal threads check whether the `Subscription` has been
+// dropped, rather than blocking for the full accept/renewal period.
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A live UPnP GENA subscription returned by [`Switch::subscribe`].
+///
+/// Receive pushed [`WemoState`]s through the `Receiver` interface (this type derefs to one).
+/// Dropping a `Subscription` unsubscribes from the device and stops its renewal and
+/// NOTIFY-listener background threads — it's the cancellation handle, not just a channel.
+pub struct Subscription {
+    events: Receiver<WemoState>,
+    alive: Arc<AtomicBool>,
+    ip_address: IpAddr,
+    port: u16,
+    sid: String,
+}
+
+impl Deref for Subscription {
+    type Target = Receiver<WemoState>;
+
+    fn deref(&self) -> &Receiver<WemoState> {
+        &self.events
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.alive.store(false, Ordering::Relaxed);
+        let _ = unsubscribe(self.ip_address, self.port, &self.sid);
+    }
+}
+
+impl Switch {
+    /// Subscribes to the device's UPnP eventing (GENA) feed and returns a [`Subscription`] that
+    /// receives a [`WemoState`] every time the device's on/off state changes.
+    ///
+    /// This starts a small embedded HTTP listener on a background thread to receive the
+    /// device's `NOTIFY` callbacks, and renews the subscription shortly before it expires
+    /// using the `SID` the device handed back. Dropping the returned `Subscription` tears both
+    /// threads down and unsubscribes from the device.
+    pub fn subscribe(&self) -> Result<Subscription> {
+        let listener = TcpListener::bind("0.0.0.0:0")?;
+        let callback_port = listener.local_addr()?.port();
+        let callback_ip = local_ip_towards(self.ip_address(), self.port())?;
+
+        let sid = send_subscribe(self.ip_address(), self.port(), callback_ip, callback_port)?;
+
+        // Shared by the listener and renewal threads so that dropping the `Subscription`
+        // promptly stops both of them, instead of the renewal thread renewing a subscription
+        // forever on a callback URL nobody reads from.
+        let alive = Arc::new(AtomicBool::new(true));
+
+        let (tx, rx) = mpsc::channel();
+        let listener_alive = Arc::clone(&alive);
+        thread::spawn(move || run_listener(listener, tx, listener_alive));
+
+        let switch = self.clone();
+        let sid_for_renew = sid.clone();
+        let renew_alive = Arc::clone(&alive);
+        thread::spawn(move || renew_loop(switch, sid_for_renew, renew_alive));
+
+        Ok(Subscription {
+            events: rx,
+            alive,
+            ip_address: self.ip_address(),
+            port: self.port(),
+            sid,
+        })
+    }
+}
+
+fn send_subscribe(ip: IpAddr, port: u16, callback_ip: IpAddr, callback_port: u16) -> Result<String> {
+    let url = format!("http://{ip}:{port}{EVENT_PATH}");
+    let callback = format!("<http://{callback_ip}:{callback_port}/>");
+
+    let response = ureq::request("SUBSCRIBE", &url)
+        .set("CALLBACK", &callback)
+        .set("NT", "upnp:event")
+        .set("TIMEOUT", &format!("Second-{SUBSCRIPTION_TIMEOUT_SECS}"))
+        .call()?;
+
+    response
+        .header("SID")
+        .map(str::to_string)
+        .ok_or(Error::MissingField("SID"))
+}
+
+fn renew_subscribe(ip: IpAddr, port: u16, sid: &str) -> Result<()> {
+    let url = format!("http://{ip}:{port}{EVENT_PATH}");
+    ureq::request("SUBSCRIBE", &url)
+        .set("SID", sid)
+        .set("TIMEOUT", &format!("Second-{SUBSCRIPTION_TIMEOUT_SECS}"))
+        .call()?;
+    Ok(())
+}
+
+/// Tells the device we're no longer interested in its events.
+fn unsubscribe(ip: IpAddr, port: u16, sid: &str) -> Result<()> {
+    let url = format!("http://{ip}:{port}{EVENT_PATH}");
+    ureq::request("UNSUBSCRIBE", &url).set("SID", sid).call()?;
+    Ok(())
+}
+
+/// Renews the subscription shortly before it times out, for as long as the device keeps
+/// answering and `alive` says the `Subscription` hasn't been dropped. Checks `alive` every
+/// [`SHUTDOWN_POLL_INTERVAL`] rather than sleeping for the whole renewal period, so dropping the
+/// `Subscription` stops this thread promptly instead of after up to `SUBSCRIPTION_TIMEOUT_SECS`.
+fn renew_loop(switch: Switch, sid: String, alive: Arc<AtomicBool>) {
+    let renew_every = Duration::from_secs(SUBSCRIPTION_TIMEOUT_SECS.saturating_sub(30));
+    let mut next_renew = Instant::now() + renew_every;
+
+    while alive.load(Ordering::Relaxed) {
+        if Instant::now() >= next_renew {
+            if renew_subscribe(switch.ip_address(), switch.port(), &sid).is_err() {
+                break;
+            }
+            next_renew = Instant::now() + renew_every;
+        }
+        thread::sleep(SHUTDOWN_POLL_INTERVAL);
+    }
+}
+
+/// Accepts `NOTIFY` callbacks from the device and forwards the state changes they report.
+/// Polls the listening socket instead of blocking on `accept` forever, so it notices `alive`
+/// going false (the `Subscription` was dropped) within [`SHUTDOWN_POLL_INTERVAL`] even if the
+/// device never sends another NOTIFY.
+fn run_listener(listener: TcpListener, tx: mpsc::Sender<WemoState>, alive: Arc<AtomicBool>) {
+    listener.set_nonblocking(true).ok();
+
+    while alive.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false).ok();
+                if let Some(state) = handle_notify(stream) {
+                    if tx.send(state).is_err() {
+                        break;
+                    }
+                }
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(SHUTDOWN_POLL_INTERVAL);
+            }
+            Err(_) => break,
+        }
+    }
+
+    alive.store(false, Ordering::Relaxed);
+}
+
+/// Reads a single `NOTIFY` request off `stream`, acknowledges it, and extracts the
+/// `BinaryState` it carries, if any.
+fn handle_notify(stream: TcpStream) -> Option<WemoState> {
+    let mut reader = BufReader::new(stream.try_clone().ok()?);
+    let mut content_length = 0usize;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let trimmed = line.trim_end();
+        if trimmed.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = trimmed.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).ok()?;
+    let body = String::from_utf8_lossy(&body);
+
+    let mut stream = stream;
+    let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+
+    let raw = soap::extract_tag(&body, "BinaryState")?;
+    let on = raw.trim().parse::<i32>().ok()? != 0;
+
+    Some(WemoState::new(on))
+}
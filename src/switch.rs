@@ -0,0 +1,224 @@
+use std::net::IpAddr;
+use std::thread;
+use std::time::Instant;
+
+use time::Duration;
+
+use crate::error::{Error, Result};
+use crate::insight::InsightParams;
+use crate::soap;
+use crate::state::WemoState;
+
+const SERVICE_BASIC_EVENT: &str = "urn:Belkin:service:basicevent:1";
+const CONTROL_BASIC_EVENT: &str = "/upnp/control/basicevent1";
+const SERVICE_INSIGHT: &str = "urn:Belkin:service:insight:1";
+const CONTROL_INSIGHT: &str = "/upnp/control/insight1";
+
+/// A handle to a single WeMo switch (or switch-like device) on the network.
+///
+/// A `Switch` only needs an address to be constructed; no network request is made until one
+/// of its methods is called. Its SOAP control URLs default to the paths most WeMo firmwares
+/// use, but [`Switch::from_setup_xml`] reads the device's actual `serviceList` instead.
+#[derive(Debug, Clone)]
+pub struct Switch {
+    ip_address: IpAddr,
+    port: u16,
+    basic_event_control: String,
+    insight_control: String,
+}
+
+impl Switch {
+    /// Builds a `Switch` that talks to the device at `ip_address:port`, assuming the default
+    /// control URLs.
+    pub fn from_dynamic_ip_and_port(ip_address: IpAddr, port: u16) -> Self {
+        Self {
+            ip_address,
+            port,
+            basic_event_control: CONTROL_BASIC_EVENT.to_string(),
+            insight_control: CONTROL_INSIGHT.to_string(),
+        }
+    }
+
+    /// Builds a `Switch` for the device at `ip_address:port`, taking its control URLs from an
+    /// already-fetched `setup.xml` body rather than assuming the defaults.
+    pub(crate) fn from_setup_xml(ip_address: IpAddr, port: u16, setup_xml: &str) -> Self {
+        let mut switch = Self::from_dynamic_ip_and_port(ip_address, port);
+
+        for service in soap::tag_blocks(setup_xml, "service") {
+            let (Some(service_type), Some(control_url)) = (
+                soap::extract_tag(service, "serviceType"),
+                soap::extract_tag(service, "controlURL"),
+            ) else {
+                continue;
+            };
+
+            if service_type == SERVICE_BASIC_EVENT {
+                switch.basic_event_control = control_url.to_string();
+            } else if service_type == SERVICE_INSIGHT {
+                switch.insight_control = control_url.to_string();
+            }
+        }
+
+        switch
+    }
+
+    /// The device's IP address.
+    pub fn ip_address(&self) -> IpAddr {
+        self.ip_address
+    }
+
+    /// The device's HTTP control port.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Fetches the device's friendly name via `setup.xml`, falling back to its address if the
+    /// request fails.
+    pub fn name(&self) -> String {
+        let url = format!("http://{}:{}/setup.xml", self.ip_address, self.port);
+        ureq::get(&url)
+            .call()
+            .ok()
+            .and_then(|r| r.into_string().ok())
+            .and_then(|body| soap::extract_tag(&body, "friendlyName").map(str::to_string))
+            .unwrap_or_else(|| format!("{}:{}", self.ip_address, self.port))
+    }
+
+    /// Reads the switch's current on/off state.
+    pub fn get_state(&self) -> Result<WemoState> {
+        let body = soap::send_action(
+            self.ip_address,
+            self.port,
+            &self.basic_event_control,
+            SERVICE_BASIC_EVENT,
+            "GetBinaryState",
+            "",
+        )?;
+
+        let raw = soap::extract_tag(&body, "BinaryState").ok_or(Error::MissingField("BinaryState"))?;
+        let on = raw.trim().parse::<i32>().map_err(|_| Error::InvalidField("BinaryState"))? != 0;
+        Ok(WemoState::new(on))
+    }
+
+    /// Like [`Switch::get_state`], but retries on failure until `timeout` has elapsed.
+    pub fn get_state_with_retry(&self, timeout: Duration) -> Result<WemoState> {
+        retry(timeout, || self.get_state())
+    }
+
+    /// Switches the device on.
+    pub fn turn_on(&self) -> Result<()> {
+        self.set_state(true)
+    }
+
+    /// Like [`Switch::turn_on`], but retries on failure until `timeout` has elapsed.
+    pub fn turn_on_with_retry(&self, timeout: Duration) -> Result<()> {
+        retry(timeout, || self.turn_on())
+    }
+
+    /// Switches the device off.
+    pub fn turn_off(&self) -> Result<()> {
+        self.set_state(false)
+    }
+
+    /// Like [`Switch::turn_off`], but retries on failure until `timeout` has elapsed.
+    pub fn turn_off_with_retry(&self, timeout: Duration) -> Result<()> {
+        retry(timeout, || self.turn_off())
+    }
+
+    /// Reads power-usage data from a WeMo Insight plug via `GetInsightParams`.
+    ///
+    /// This only succeeds against Insight-model devices; other switches don't implement the
+    /// `insight` service and will return an error.
+    pub fn get_insight_params(&self) -> Result<InsightParams> {
+        let body = soap::send_action(
+            self.ip_address,
+            self.port,
+            &self.insight_control,
+            SERVICE_INSIGHT,
+            "GetInsightParams",
+            "",
+        )?;
+
+        let raw =
+            soap::extract_tag(&body, "InsightParams").ok_or(Error::MissingField("InsightParams"))?;
+        InsightParams::parse(raw)
+    }
+
+    fn set_state(&self, on: bool) -> Result<()> {
+        let body = format!("<BinaryState>{}</BinaryState>", on as i32);
+        soap::send_action(
+            self.ip_address,
+            self.port,
+            &self.basic_event_control,
+            SERVICE_BASIC_EVENT,
+            "SetBinaryState",
+            &body,
+        )?;
+        Ok(())
+    }
+}
+
+/// Retries `f` with a short delay between attempts until it succeeds or `timeout` elapses.
+pub(crate) fn retry<T>(timeout: Duration, mut f: impl FnMut() -> Result<T>) -> Result<T> {
+    let std_timeout = timeout.try_into().unwrap_or(std::time::Duration::ZERO);
+    let deadline = Instant::now() + std_timeout;
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if Instant::now() >= deadline {
+                    return Err(err);
+                }
+                thread::sleep(std::time::Duration::from_millis(500));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 50));
+
+    #[test]
+    fn from_setup_xml_picks_up_control_urls_from_service_list() {
+        let setup_xml = "<root><device><serviceList>\
+             <service><serviceType>urn:Belkin:service:basicevent:1</serviceType>\
+             <controlURL>/upnp/control/basicevent1</controlURL></service>\
+             <service><serviceType>urn:Belkin:service:insight:1</serviceType>\
+             <controlURL>/upnp/control/insight1</controlURL></service>\
+             <service><serviceType>urn:Belkin:service:metainfo:1</serviceType>\
+             <controlURL>/upnp/control/metainfo1</controlURL></service>\
+             </serviceList></device></root>";
+
+        let switch = Switch::from_setup_xml(IP, 49153, setup_xml);
+
+        assert_eq!(switch.basic_event_control, "/upnp/control/basicevent1");
+        assert_eq!(switch.insight_control, "/upnp/control/insight1");
+    }
+
+    #[test]
+    fn from_setup_xml_keeps_defaults_for_services_not_listed() {
+        let setup_xml = "<root><device><serviceList>\
+             <service><serviceType>urn:Belkin:service:basicevent:1</serviceType>\
+             <controlURL>/custom/basicevent1</controlURL></service>\
+             </serviceList></device></root>";
+
+        let switch = Switch::from_setup_xml(IP, 49153, setup_xml);
+
+        assert_eq!(switch.basic_event_control, "/custom/basicevent1");
+        assert_eq!(switch.insight_control, CONTROL_INSIGHT);
+    }
+
+    #[test]
+    fn from_setup_xml_keeps_defaults_when_service_list_is_malformed() {
+        let setup_xml = "<root><device><serviceList><service><serviceType>urn:Belkin:service:basicevent:1";
+
+        let switch = Switch::from_setup_xml(IP, 49153, setup_xml);
+
+        assert_eq!(switch.basic_event_control, CONTROL_BASIC_EVENT);
+        assert_eq!(switch.insight_control, CONTROL_INSIGHT);
+    }
+}
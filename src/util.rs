@@ -0,0 +1,10 @@
+use std::net::{IpAddr, UdpSocket};
+
+use crate::error::Result;
+
+/// Finds the local address used to reach `target:port`, e.g. for advertising a callback URL.
+pub(crate) fn local_ip_towards(target: IpAddr, port: u16) -> Result<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0")?;
+    socket.connect((target, port))?;
+    Ok(socket.local_addr()?.ip())
+}